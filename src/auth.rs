@@ -0,0 +1,178 @@
+/* Pi-hole: A black hole for Internet advertisements
+*  (c) 2018 Pi-hole, LLC (https://pi-hole.net)
+*  Network-wide ad blocking via your own hardware.
+*
+*  API
+*  Session-based authentication
+*
+*  This file is copyright under the latest version of the EUPL.
+*  Please see LICENSE file for your rights under this license. */
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use rand::{thread_rng, Rng};
+use rocket::State;
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Request};
+use rocket::Outcome;
+use rocket_contrib::Json;
+
+use util;
+use config::{Config, PiholeFile};
+
+/// The HTTP header a client uses to present either the configured password or a
+/// session ID.
+const AUTH_HEADER: &str = "X-Pi-hole-Authenticate";
+
+/// The set of currently valid session IDs. Stored behind a `Mutex` and shared
+/// as Rocket managed state.
+pub struct SessionStore {
+    sessions: Mutex<HashSet<String>>
+}
+
+impl SessionStore {
+    /// Create an empty session store
+    pub fn new() -> SessionStore {
+        SessionStore { sessions: Mutex::new(HashSet::new()) }
+    }
+
+    /// Create a new session and return its SID
+    fn create(&self) -> String {
+        let sid = new_sid();
+        self.sessions.lock().unwrap().insert(sid.clone());
+        sid
+    }
+
+    /// Check if a SID refers to a live session
+    fn is_valid(&self, sid: &str) -> bool {
+        self.sessions.lock().unwrap().contains(sid)
+    }
+
+    /// Invalidate a session, returning whether it existed
+    fn destroy(&self, sid: &str) -> bool {
+        self.sessions.lock().unwrap().remove(sid)
+    }
+}
+
+/// Generate a session ID from 16 bytes of cryptographically secure randomness,
+/// rendered as hex. This leaves the SID infeasible to guess or brute-force.
+fn new_sid() -> String {
+    let mut bytes = [0u8; 16];
+    thread_rng().fill(&mut bytes[..]);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// If the CLI password file is readable, return its contents. Local CLI tooling
+/// relies on this to authenticate without embedding the admin password.
+fn cli_password(config: &Config) -> Option<String> {
+    let mut file = config.read_file(PiholeFile::CliPassword).ok()?;
+    let mut password = String::new();
+    file.read_to_string(&mut password).ok()?;
+    Some(password.trim().to_owned())
+}
+
+/// Request guard exposing the client's remote socket address when Rocket was
+/// able to determine it. Used to confine the silent CLI login to local callers.
+pub struct ClientAddr(Option<SocketAddr>);
+
+impl ClientAddr {
+    /// Whether the request originated from the loopback interface
+    fn is_loopback(&self) -> bool {
+        self.0.map_or(false, |addr| addr.ip().is_loopback())
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for ClientAddr {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<ClientAddr, ()> {
+        Outcome::Success(ClientAddr(request.remote()))
+    }
+}
+
+/// A request guard which succeeds when the request carries either the
+/// configured password or a valid session ID in the [`AUTH_HEADER`] header.
+///
+/// [`AUTH_HEADER`]: constant.AUTH_HEADER.html
+pub struct User;
+
+impl<'a, 'r> FromRequest<'a, 'r> for User {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<User, ()> {
+        let credential = match request.headers().get_one(AUTH_HEADER) {
+            Some(credential) => credential,
+            None => return Outcome::Failure((Status::Unauthorized, ()))
+        };
+
+        let config = request.guard::<State<Config>>().unwrap();
+        let sessions = request.guard::<State<SessionStore>>().unwrap();
+
+        // Accept either the admin password or a live session ID
+        if credential == config.web_password() || sessions.is_valid(credential) {
+            Outcome::Success(User)
+        } else {
+            Outcome::Failure((Status::Unauthorized, ()))
+        }
+    }
+}
+
+/// The body accepted by the login endpoint
+#[derive(Deserialize)]
+pub struct Login {
+    password: String
+}
+
+/// Log in and obtain a session ID. The configured password may be supplied in
+/// the body, or — for local CLI tooling only — authentication succeeds silently
+/// when the CLI password file is readable and its contents equal the configured
+/// password.
+#[post("/auth", data = "<login>")]
+pub fn login(
+    config: State<Config>,
+    sessions: State<SessionStore>,
+    client: ClientAddr,
+    login: Option<Json<Login>>
+) -> util::Reply {
+    let authenticated = match login {
+        Some(login) => login.0.password == config.web_password(),
+        // The silent path proves the *server* can read the CLI password file,
+        // not that the *caller* can. Only honor it for loopback requests, where
+        // the caller is local CLI tooling; a remote client must send the
+        // password explicitly.
+        None => {
+            client.is_loopback()
+                && cli_password(&config).map_or(false, |pw| pw == config.web_password())
+        }
+    };
+
+    if !authenticated {
+        return Err(util::Error::Unauthorized);
+    }
+
+    util::reply_data(json!({ "sid": sessions.create() }))
+}
+
+/// Log out, invalidating the session ID presented in the auth header
+#[delete("/auth")]
+pub fn logout(_user: User, request_sid: Sid, sessions: State<SessionStore>) -> util::Reply {
+    sessions.destroy(&request_sid.0);
+    util::reply_success()
+}
+
+/// Request guard which extracts the raw session ID from the auth header
+pub struct Sid(String);
+
+impl<'a, 'r> FromRequest<'a, 'r> for Sid {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Sid, ()> {
+        match request.headers().get_one(AUTH_HEADER) {
+            Some(sid) => Outcome::Success(Sid(sid.to_owned())),
+            None => Outcome::Failure((Status::Unauthorized, ()))
+        }
+    }
+}
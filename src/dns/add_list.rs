@@ -0,0 +1,79 @@
+/* Pi-hole: A black hole for Internet advertisements
+*  (c) 2018 Pi-hole, LLC (https://pi-hole.net)
+*  Network-wide ad blocking via your own hardware.
+*
+*  API
+*  Endpoints for adding domains to lists
+*
+*  This file is copyright under the latest version of the EUPL.
+*  Please see LICENSE file for your rights under this license. */
+
+use config::{Config, PiholeFile};
+use dns::common::reload_gravity;
+use dns::list::{batch_reply, AddDomain, AddResult, List};
+use rocket::State;
+use rocket_contrib::Json;
+use util;
+use auth::User;
+use ftl::FtlConnectionType;
+
+/// Turn one result of a batch add into the reason string reported to the caller
+fn add_reason(result: &AddResult) -> &'static str {
+    match *result {
+        AddResult::Added => "added",
+        AddResult::AlreadyExists => "already_exists",
+        AddResult::InvalidDomain => "invalid_domain"
+    }
+}
+
+/// Add one or more domains to the whitelist
+#[post("/dns/whitelist", data = "<domains>")]
+pub fn add_whitelist(
+    _auth: User,
+    config: State<Config>,
+    domains: Json<Vec<AddDomain>>
+) -> util::Reply {
+    let results = List::White.add_many(&domains.0, &config)?;
+    let added = results.iter().any(|&(_, ref result)| *result == AddResult::Added);
+
+    if added {
+        reload_gravity(PiholeFile::Whitelist, &config)?;
+    }
+
+    batch_reply(&results, add_reason)
+}
+
+/// Add one or more domains to the blacklist
+#[post("/dns/blacklist", data = "<domains>")]
+pub fn add_blacklist(
+    _auth: User,
+    config: State<Config>,
+    domains: Json<Vec<AddDomain>>
+) -> util::Reply {
+    let results = List::Black.add_many(&domains.0, &config)?;
+    let added = results.iter().any(|&(_, ref result)| *result == AddResult::Added);
+
+    if added {
+        reload_gravity(PiholeFile::Blacklist, &config)?;
+    }
+
+    batch_reply(&results, add_reason)
+}
+
+/// Add one or more domains to the regex list
+#[post("/dns/regexlist", data = "<domains>")]
+pub fn add_regexlist(
+    _auth: User,
+    config: State<Config>,
+    ftl: State<FtlConnectionType>,
+    domains: Json<Vec<AddDomain>>
+) -> util::Reply {
+    let results = List::Regex.add_many(&domains.0, &config)?;
+    let added = results.iter().any(|&(_, ref result)| *result == AddResult::Added);
+
+    if added {
+        ftl.connect("recompile-regex")?.expect_eom()?;
+    }
+
+    batch_reply(&results, add_reason)
+}
@@ -10,12 +10,22 @@
 
 use config::{Config, PiholeFile};
 use dns::common::reload_gravity;
-use dns::list::List;
+use dns::list::{batch_reply, List, RemoveResult};
 use rocket::State;
+use rocket_contrib::Json;
 use util;
 use auth::User;
 use ftl::FtlConnectionType;
 
+/// Turn one result of a batch remove into the reason string reported to the caller
+fn remove_reason(result: &RemoveResult) -> &'static str {
+    match *result {
+        RemoveResult::Removed => "removed",
+        RemoveResult::NotFound => "not_found",
+        RemoveResult::InvalidDomain => "invalid_domain"
+    }
+}
+
 /// Delete a domain from the whitelist
 #[delete("/dns/whitelist/<domain>")]
 pub fn delete_whitelist(_auth: User, config: State<Config>, domain: String) -> util::Reply {
@@ -45,6 +55,83 @@ pub fn delete_regexlist(
     util::reply_success()
 }
 
+/// Delete one or more domains from the whitelist
+#[post("/dns/whitelist/delete", data = "<domains>")]
+pub fn delete_whitelist_many(
+    _auth: User,
+    config: State<Config>,
+    domains: Json<Vec<String>>
+) -> util::Reply {
+    let results = List::White.remove_many(&domains.0, &config)?;
+
+    if results.iter().any(|&(_, ref r)| *r == RemoveResult::Removed) {
+        reload_gravity(PiholeFile::Whitelist, &config)?;
+    }
+
+    batch_reply(&results, remove_reason)
+}
+
+/// Delete one or more domains from the blacklist
+#[post("/dns/blacklist/delete", data = "<domains>")]
+pub fn delete_blacklist_many(
+    _auth: User,
+    config: State<Config>,
+    domains: Json<Vec<String>>
+) -> util::Reply {
+    let results = List::Black.remove_many(&domains.0, &config)?;
+
+    if results.iter().any(|&(_, ref r)| *r == RemoveResult::Removed) {
+        reload_gravity(PiholeFile::Blacklist, &config)?;
+    }
+
+    batch_reply(&results, remove_reason)
+}
+
+/// Delete one or more domains from the regex list
+#[post("/dns/regexlist/delete", data = "<domains>")]
+pub fn delete_regexlist_many(
+    _auth: User,
+    config: State<Config>,
+    ftl: State<FtlConnectionType>,
+    domains: Json<Vec<String>>
+) -> util::Reply {
+    let results = List::Regex.remove_many(&domains.0, &config)?;
+
+    if results.iter().any(|&(_, ref r)| *r == RemoveResult::Removed) {
+        ftl.connect("recompile-regex")?.expect_eom()?;
+    }
+
+    batch_reply(&results, remove_reason)
+}
+
+/// Clear every domain from the whitelist
+#[delete("/dns/whitelist")]
+pub fn nuke_whitelist(_auth: User, config: State<Config>) -> util::Reply {
+    List::White.clear(&config)?;
+    reload_gravity(PiholeFile::Whitelist, &config)?;
+    util::reply_success()
+}
+
+/// Clear every domain from the blacklist
+#[delete("/dns/blacklist")]
+pub fn nuke_blacklist(_auth: User, config: State<Config>) -> util::Reply {
+    List::Black.clear(&config)?;
+    reload_gravity(PiholeFile::Blacklist, &config)?;
+    util::reply_success()
+}
+
+/// Clear every domain from the regex list
+#[delete("/dns/regexlist")]
+pub fn nuke_regexlist(
+    _auth: User,
+    config: State<Config>,
+    ftl: State<FtlConnectionType>
+) -> util::Reply {
+    List::Regex.clear(&config)?;
+    ftl.connect("recompile-regex")?.expect_eom()?;
+    util::reply_success()
+}
+
 #[cfg(test)]
 mod test {
     use testing::{TestBuilder, write_eom};
@@ -65,6 +152,24 @@ mod test {
             .test();
     }
 
+    #[test]
+    fn test_nuke_whitelist() {
+        TestBuilder::new()
+            .endpoint("/admin/api/dns/whitelist")
+            .method(Method::Delete)
+            .file_expect(
+                PiholeFile::Whitelist,
+                "example.com\nexample.net\n",
+                ""
+            )
+            .expect_json(
+                json!({
+                    "status": "success"
+                })
+            )
+            .test();
+    }
+
     #[test]
     fn test_delete_blacklist() {
         TestBuilder::new()
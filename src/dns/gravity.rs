@@ -0,0 +1,167 @@
+/* Pi-hole: A black hole for Internet advertisements
+*  (c) 2018 Pi-hole, LLC (https://pi-hole.net)
+*  Network-wide ad blocking via your own hardware.
+*
+*  API
+*  SQLite gravity-database backend for DNS lists
+*
+*  This file is copyright under the latest version of the EUPL.
+*  Please see LICENSE file for your rights under this license. */
+
+use std::collections::HashMap;
+
+use rusqlite::Connection;
+
+use util;
+use config::{Config, PiholeFile};
+use dns::groups;
+use dns::list::{List, ListEntry};
+
+/// Map a list onto the `domainlist.type` value used by the gravity database.
+/// These match the values written by the Pi-hole gravity migration SQL.
+fn domain_type(list: &List) -> i32 {
+    match *list {
+        List::White => 0,
+        List::Black => 1,
+        List::Regex => 3
+    }
+}
+
+/// Open a connection to the gravity database
+pub fn connect(config: &Config) -> Result<Connection, util::Error> {
+    Connection::open(config.file_location(PiholeFile::Gravity))
+        .map_err(|_| util::Error::Unknown)
+}
+
+/// Read every entry of the given type out of the gravity database
+pub fn get(list: &List, config: &Config) -> Result<Vec<ListEntry>, util::Error> {
+    let db = connect(config)?;
+
+    let mut statement = db
+        .prepare("SELECT id, domain, comment, enabled FROM domainlist WHERE type = ?1")
+        .map_err(|_| util::Error::Unknown)?;
+
+    let rows = statement
+        .query_map(&[&domain_type(list)], |row| {
+            let id: i32 = row.get(0);
+            ListEntry {
+                domain: row.get(1),
+                comment: row.get(2),
+                enabled: row.get::<_, i32>(3) != 0,
+                groups: Vec::new(),
+                id
+            }
+        })
+        .map_err(|_| util::Error::Unknown)?
+        .filter_map(|entry| entry.ok());
+
+    let mut entries: Vec<ListEntry> = rows.collect();
+
+    // Batch-load the whole association table for this list type in one query
+    // rather than issuing a lookup per entry, then fold it into each entry
+    let mut memberships = group_memberships(list, &db)?;
+    for entry in &mut entries {
+        if let Some(groups) = memberships.remove(&entry.id) {
+            entry.groups = groups;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Load every domain-to-group association for the given list type in a single
+/// query, keyed by the domainlist row ID.
+fn group_memberships(
+    list: &List,
+    db: &Connection
+) -> Result<HashMap<i32, Vec<i32>>, util::Error> {
+    let mut statement = db
+        .prepare(
+            "SELECT dlg.domainlist_id, dlg.group_id \
+             FROM domainlist_by_group dlg \
+             JOIN domainlist dl ON dl.id = dlg.domainlist_id \
+             WHERE dl.type = ?1"
+        )
+        .map_err(|_| util::Error::Unknown)?;
+
+    let rows = statement
+        .query_map(&[&domain_type(list)], |row| {
+            (row.get::<_, i32>(0), row.get::<_, i32>(1))
+        })
+        .map_err(|_| util::Error::Unknown)?
+        .filter_map(|row| row.ok());
+
+    let mut memberships: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (domain_id, group_id) in rows {
+        memberships.entry(domain_id).or_insert_with(Vec::new).push(group_id);
+    }
+
+    Ok(memberships)
+}
+
+/// Add a domain, with an optional comment and group associations, to the
+/// gravity database. Every new entry is also placed in the default group 0,
+/// mirroring the insert trigger in the gravity migration SQL.
+pub fn add(
+    list: &List,
+    domain: &str,
+    comment: Option<&str>,
+    group_ids: &[i32],
+    config: &Config
+) -> Result<(), util::Error> {
+    let db = connect(config)?;
+
+    // Reject references to groups which do not exist before touching the list
+    for &group_id in group_ids {
+        if !groups::exists(group_id, &db)? {
+            return Err(util::Error::NotFound);
+        }
+    }
+
+    db.execute(
+        "INSERT INTO domainlist (type, domain, enabled, comment) VALUES (?1, ?2, 1, ?3)",
+        &[&domain_type(list), &domain, &comment]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    let domain_id = db.last_insert_rowid() as i32;
+
+    // The default group 0 always applies, plus any explicitly requested groups
+    db.execute(
+        "INSERT OR IGNORE INTO domainlist_by_group (domainlist_id, group_id) VALUES (?1, 0)",
+        &[&domain_id]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    for &group_id in group_ids {
+        db.execute(
+            "INSERT OR IGNORE INTO domainlist_by_group (domainlist_id, group_id) \
+             VALUES (?1, ?2)",
+            &[&domain_id, &group_id]
+        ).map_err(|_| util::Error::Unknown)?;
+    }
+
+    Ok(())
+}
+
+/// Remove a domain from the gravity database
+pub fn remove(list: &List, domain: &str, config: &Config) -> Result<(), util::Error> {
+    let db = connect(config)?;
+
+    db.execute(
+        "DELETE FROM domainlist WHERE type = ?1 AND domain = ?2",
+        &[&domain_type(list), &domain]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    Ok(())
+}
+
+/// Remove every entry of the given type from the gravity database
+pub fn clear(list: &List, config: &Config) -> Result<(), util::Error> {
+    let db = connect(config)?;
+
+    db.execute(
+        "DELETE FROM domainlist WHERE type = ?1",
+        &[&domain_type(list)]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    Ok(())
+}
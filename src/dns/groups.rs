@@ -0,0 +1,138 @@
+/* Pi-hole: A black hole for Internet advertisements
+*  (c) 2018 Pi-hole, LLC (https://pi-hole.net)
+*  Network-wide ad blocking via your own hardware.
+*
+*  API
+*  Group subsystem for scoping list entries to clients
+*
+*  This file is copyright under the latest version of the EUPL.
+*  Please see LICENSE file for your rights under this license. */
+
+use rusqlite::Connection;
+
+use config::Config;
+use dns::gravity;
+use rocket::State;
+use rocket_contrib::Json;
+use util;
+use auth::User;
+
+/// A client group in the gravity database. Group 0 ("Default") always exists.
+#[derive(Serialize)]
+pub struct Group {
+    pub id: i32,
+    pub enabled: bool,
+    pub name: String,
+    pub description: Option<String>
+}
+
+/// The body accepted when creating or updating a group
+#[derive(Deserialize)]
+pub struct GroupInput {
+    enabled: bool,
+    name: String,
+    description: Option<String>
+}
+
+/// Check whether a group with the given ID exists
+pub fn exists(group_id: i32, db: &Connection) -> Result<bool, util::Error> {
+    let count: i32 = db
+        .query_row(
+            "SELECT COUNT(*) FROM \"group\" WHERE id = ?1",
+            &[&group_id],
+            |row| row.get(0)
+        )
+        .map_err(|_| util::Error::Unknown)?;
+
+    Ok(count != 0)
+}
+
+/// Re-create the default group 0 if it is missing. This matches the trigger in
+/// the gravity migration SQL which guarantees the default group always exists.
+fn ensure_default_group(db: &Connection) -> Result<(), util::Error> {
+    db.execute(
+        "INSERT OR IGNORE INTO \"group\" (id, enabled, name, description) \
+         VALUES (0, 1, 'Default', 'The default group')",
+        &[]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    Ok(())
+}
+
+/// Read every group out of the gravity database
+#[get("/groups")]
+pub fn get_groups(_auth: User, config: State<Config>) -> util::Reply {
+    let db = gravity::connect(&config)?;
+    ensure_default_group(&db)?;
+
+    let mut statement = db
+        .prepare("SELECT id, enabled, name, description FROM \"group\" ORDER BY id")
+        .map_err(|_| util::Error::Unknown)?;
+
+    let groups: Vec<Group> = statement
+        .query_map(&[], |row| Group {
+            id: row.get(0),
+            enabled: row.get::<_, i32>(1) != 0,
+            name: row.get(2),
+            description: row.get(3)
+        })
+        .map_err(|_| util::Error::Unknown)?
+        .filter_map(|group| group.ok())
+        .collect();
+
+    util::reply_data(groups)
+}
+
+/// Create a new group
+#[post("/groups", data = "<group>")]
+pub fn add_group(_auth: User, config: State<Config>, group: Json<GroupInput>) -> util::Reply {
+    let db = gravity::connect(&config)?;
+
+    db.execute(
+        "INSERT INTO \"group\" (enabled, name, description) VALUES (?1, ?2, ?3)",
+        &[&(group.0.enabled as i32), &group.0.name, &group.0.description]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    util::reply_success()
+}
+
+/// Update an existing group
+#[put("/groups/<id>", data = "<group>")]
+pub fn update_group(
+    _auth: User,
+    config: State<Config>,
+    id: i32,
+    group: Json<GroupInput>
+) -> util::Reply {
+    let db = gravity::connect(&config)?;
+
+    if !exists(id, &db)? {
+        return Err(util::Error::NotFound);
+    }
+
+    db.execute(
+        "UPDATE \"group\" SET enabled = ?1, name = ?2, description = ?3 WHERE id = ?4",
+        &[&(group.0.enabled as i32), &group.0.name, &group.0.description, &id]
+    ).map_err(|_| util::Error::Unknown)?;
+
+    util::reply_success()
+}
+
+/// Delete a group. The default group 0 is re-created immediately if it is the
+/// target, preserving the always-present default invariant.
+#[delete("/groups/<id>")]
+pub fn delete_group(_auth: User, config: State<Config>, id: i32) -> util::Reply {
+    let db = gravity::connect(&config)?;
+
+    if !exists(id, &db)? {
+        return Err(util::Error::NotFound);
+    }
+
+    db.execute("DELETE FROM \"group\" WHERE id = ?1", &[&id])
+        .map_err(|_| util::Error::Unknown)?;
+
+    // The default group must always exist
+    ensure_default_group(&db)?;
+
+    util::reply_success()
+}
@@ -13,12 +13,59 @@ use std::io::{self, BufReader, BufWriter};
 
 use util;
 use dns::common::{is_valid_domain, is_valid_regex};
+use dns::gravity;
 use config::{Config, PiholeFile};
 
 pub enum List {
     White, Black, Regex
 }
 
+/// A single entry of a list. When the list is backed by the gravity database
+/// each entry carries an optional comment and an enabled flag; the flat-file
+/// backend reports every entry as enabled with no comment.
+#[derive(Serialize)]
+pub struct ListEntry {
+    pub domain: String,
+    pub comment: Option<String>,
+    pub enabled: bool,
+    /// The IDs of the groups this entry belongs to (always empty for the
+    /// flat-file backend, which has no group model)
+    pub groups: Vec<i32>,
+    /// The gravity-database row ID, used to resolve group membership. Not
+    /// serialized; it is an implementation detail of the gravity backend.
+    #[serde(skip)]
+    pub id: i32
+}
+
+/// The input accepted when adding a domain to a list. Only the domain is
+/// required; an optional comment and group associations may accompany it and
+/// are persisted by the gravity-database backend (the flat-file backend has no
+/// place to store them and ignores them).
+#[derive(Deserialize)]
+pub struct AddDomain {
+    pub domain: String,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub groups: Vec<i32>
+}
+
+/// The outcome of trying to add a single domain as part of a batch operation
+#[derive(PartialEq)]
+pub enum AddResult {
+    Added,
+    AlreadyExists,
+    InvalidDomain
+}
+
+/// The outcome of trying to remove a single domain as part of a batch operation
+#[derive(PartialEq)]
+pub enum RemoveResult {
+    Removed,
+    NotFound,
+    InvalidDomain
+}
+
 impl List {
     /// Get the associated `PiholeFile`
     fn file(&self) -> PiholeFile {
@@ -37,8 +84,32 @@ impl List {
         }
     }
 
-    /// Read in the domains from the list
+    /// Read in the domains of the list. This keeps the original `Vec<String>`
+    /// contract that the list-read and status endpoints depend on: only the
+    /// active (enabled) domains are returned, matching what the flat-file backend
+    /// ever held. Callers which need per-entry comments, the enabled flag or
+    /// group membership use [`get_entries`] instead.
+    ///
+    /// [`get_entries`]: #method.get_entries
     pub fn get(&self, config: &Config) -> Result<Vec<String>, util::Error> {
+        Ok(
+            self.get_entries(config)?
+                .into_iter()
+                .filter(|entry| entry.enabled)
+                .map(|entry| entry.domain)
+                .collect()
+        )
+    }
+
+    /// Read in the full entries of the list. When the gravity database is in use
+    /// the entries carry their stored comment, enabled flag and group
+    /// membership; otherwise every line of the flat file becomes an enabled
+    /// entry with no comment.
+    pub fn get_entries(&self, config: &Config) -> Result<Vec<ListEntry>, util::Error> {
+        if config.use_gravity_db() {
+            return gravity::get(self, config);
+        }
+
         let file = match config.read_file(self.file()) {
             Ok(f) => f,
             Err(e) => {
@@ -56,12 +127,27 @@ impl List {
                 .lines()
                 .filter_map(|line| line.ok())
                 .filter(|line| line.len() != 0)
+                .map(|domain| ListEntry {
+                    domain,
+                    comment: None,
+                    enabled: true,
+                    groups: Vec::new(),
+                    id: 0
+                })
                 .collect()
         )
     }
 
-    /// Add a domain to the list
-    pub fn add(&self, domain: &str, config: &Config) -> Result<(), util::Error> {
+    /// Add a domain to the list, optionally with a comment and group
+    /// associations. Comments and groups are only persisted when the gravity
+    /// database backend is in use.
+    pub fn add(
+        &self,
+        domain: &str,
+        comment: Option<&str>,
+        groups: &[i32],
+        config: &Config
+    ) -> Result<(), util::Error> {
         // Check if it's a valid domain before doing anything
         if !self.accepts(domain) {
             return Err(util::Error::InvalidDomain);
@@ -72,6 +158,10 @@ impl List {
             return Err(util::Error::AlreadyExists);
         }
 
+        if config.use_gravity_db() {
+            return gravity::add(self, domain, comment, groups, config);
+        }
+
         // Open the list file in append mode (and create it if it doesn't exist)
         let mut file = config.write_file(self.file(), true)?;
 
@@ -81,6 +171,124 @@ impl List {
         Ok(())
     }
 
+    /// Add several domains to the list at once. The file is read a single time,
+    /// every entry is validated against the in-memory list (and the entries
+    /// already queued for addition), and the file is rewritten once at the end.
+    /// A per-domain result is returned so callers can report which entries were
+    /// invalid or already present instead of aborting on the first failure.
+    /// Each entry may carry a comment and group associations, persisted by the
+    /// gravity-database backend.
+    pub fn add_many(
+        &self,
+        domains: &[AddDomain],
+        config: &Config
+    ) -> Result<Vec<(String, AddResult)>, util::Error> {
+        let mut list = self.get(config)?;
+        let mut results = Vec::with_capacity(domains.len());
+
+        for entry in domains {
+            let result = if !self.accepts(&entry.domain) {
+                AddResult::InvalidDomain
+            } else if list.contains(&entry.domain) {
+                AddResult::AlreadyExists
+            } else {
+                list.push(entry.domain.clone());
+                AddResult::Added
+            };
+
+            results.push((entry.domain.clone(), result));
+        }
+
+        // The gravity database inserts each new entry individually, carrying its
+        // comment and group associations
+        if config.use_gravity_db() {
+            for (entry, &(_, ref result)) in domains.iter().zip(results.iter()) {
+                if *result == AddResult::Added {
+                    let comment = entry.comment.as_ref().map(String::as_str);
+                    gravity::add(self, &entry.domain, comment, &entry.groups, config)?;
+                }
+            }
+
+            return Ok(results);
+        }
+
+        // Only rewrite the file if something was actually added
+        if results.iter().any(|&(_, ref result)| *result == AddResult::Added) {
+            let file = config.write_file(self.file(), false)?;
+            let mut writer = BufWriter::new(file);
+
+            for domain in &list {
+                writeln!(writer, "{}", domain)?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Remove several domains from the list at once. Like [`add_many`], the file
+    /// is read once and rewritten once, and a per-domain result is returned.
+    ///
+    /// [`add_many`]: #method.add_many
+    pub fn remove_many(
+        &self,
+        domains: &[String],
+        config: &Config
+    ) -> Result<Vec<(String, RemoveResult)>, util::Error> {
+        let mut list = self.get(config)?;
+        let mut results = Vec::with_capacity(domains.len());
+
+        for domain in domains {
+            let result = if !self.accepts(domain) {
+                RemoveResult::InvalidDomain
+            } else if let Some(index) = list.iter().position(|item| item == domain) {
+                list.remove(index);
+                RemoveResult::Removed
+            } else {
+                RemoveResult::NotFound
+            };
+
+            results.push((domain.to_owned(), result));
+        }
+
+        // The gravity database deletes each entry individually
+        if config.use_gravity_db() {
+            for &(ref domain, ref result) in &results {
+                if *result == RemoveResult::Removed {
+                    gravity::remove(self, domain, config)?;
+                }
+            }
+
+            return Ok(results);
+        }
+
+        // Only rewrite the file if something was actually removed
+        if results.iter().any(|&(_, ref result)| *result == RemoveResult::Removed) {
+            let file = config.write_file(self.file(), false)?;
+            let mut writer = BufWriter::new(file);
+
+            for domain in &list {
+                writeln!(writer, "{}", domain)?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Remove every entry from the list at once. For the flat-file backend this
+    /// truncates the backing file; for the gravity database it deletes every
+    /// entry of this list's type.
+    pub fn clear(&self, config: &Config) -> Result<(), util::Error> {
+        if config.use_gravity_db() {
+            return gravity::clear(self, config);
+        }
+
+        // Opening the file without appending truncates it; writing nothing
+        // leaves it empty
+        config.write_file(self.file(), false)?;
+
+        Ok(())
+    }
+
     /// Try to remove a domain from the list, but it is not an error if the domain does not exist
     pub fn try_remove(&self, domain: &str, config: &Config) -> Result<(), util::Error> {
         match self.remove(domain, config) {
@@ -110,6 +318,10 @@ impl List {
             return Err(util::Error::NotFound);
         }
 
+        if config.use_gravity_db() {
+            return gravity::remove(self, domain, config);
+        }
+
         // Open the list file (and create it if it doesn't exist). This will truncate the list so
         // we can add all the domains except the one we are deleting
         let file = config.write_file(self.file(), false)?;
@@ -123,3 +335,21 @@ impl List {
         Ok(())
     }
 }
+
+/// Build the structured per-domain JSON reply shared by the batch add and
+/// remove endpoints. The caller supplies the mapping from its result type to
+/// the reason string reported for each domain, so the two endpoints cannot
+/// drift in shape.
+pub fn batch_reply<T, F>(results: &[(String, T)], reason: F) -> util::Reply
+where
+    F: Fn(&T) -> &'static str
+{
+    let results: Vec<_> = results
+        .iter()
+        .map(|&(ref domain, ref result)| {
+            json!({ "domain": domain, "result": reason(result) })
+        })
+        .collect();
+
+    util::reply_data(json!({ "results": results }))
+}
@@ -0,0 +1,134 @@
+/* Pi-hole: A black hole for Internet advertisements
+*  (c) 2018 Pi-hole, LLC (https://pi-hole.net)
+*  Network-wide ad blocking via your own hardware.
+*
+*  API
+*  Endpoints for adding and removing wildcard domains
+*
+*  This file is copyright under the latest version of the EUPL.
+*  Please see LICENSE file for your rights under this license. */
+
+use config::Config;
+use dns::list::List;
+use rocket::State;
+use rocket_contrib::Json;
+use util;
+use auth::User;
+use ftl::FtlConnectionType;
+
+/// Represents a request to add a wildcard domain
+#[derive(Deserialize)]
+pub struct WildcardDomain {
+    domain: String
+}
+
+/// The regex metacharacters which must be escaped when building the anchored
+/// expression. This mirrors the `-wild` behavior from the shell tooling.
+const META_CHARACTERS: &[char] = &[
+    ']', '\\', '.', '|', '$', '(', ')', '{', '}', '?', '+', '*', '^', '/'
+];
+
+/// Transform a plain domain into the anchored regex used to match the domain
+/// and all of its subdomains. The domain is lowercased, any leading dots are
+/// stripped, the regex metacharacters are escaped, and the result is wrapped as
+/// `((^)|(\.))<escaped>$`.
+fn wildcard_regex(domain: &str) -> String {
+    let trimmed = domain.to_lowercase();
+    let trimmed = trimmed.trim_start_matches('.');
+
+    let mut escaped = String::with_capacity(trimmed.len());
+    for character in trimmed.chars() {
+        if META_CHARACTERS.contains(&character) {
+            escaped.push('\\');
+        }
+
+        escaped.push(character);
+    }
+
+    format!("((^)|(\\.)){}$", escaped)
+}
+
+/// Add a wildcard domain to the regex list
+#[post("/dns/wildcard", data = "<domain_input>")]
+pub fn add_wildcard(
+    _auth: User,
+    config: State<Config>,
+    ftl: State<FtlConnectionType>,
+    domain_input: Json<WildcardDomain>
+) -> util::Reply {
+    List::Regex.add(&wildcard_regex(&domain_input.0.domain), None, &[], &config)?;
+    ftl.connect("recompile-regex")?.expect_eom()?;
+    util::reply_success()
+}
+
+/// Delete a wildcard domain from the regex list
+#[delete("/dns/wildcard/<domain>")]
+pub fn delete_wildcard(
+    _auth: User,
+    config: State<Config>,
+    ftl: State<FtlConnectionType>,
+    domain: String
+) -> util::Reply {
+    List::Regex.remove(&wildcard_regex(&domain), &config)?;
+    ftl.connect("recompile-regex")?.expect_eom()?;
+    util::reply_success()
+}
+
+#[cfg(test)]
+mod test {
+    use super::wildcard_regex;
+    use testing::{TestBuilder, write_eom};
+    use config::PiholeFile;
+    use rocket::http::Method;
+
+    #[test]
+    fn test_wildcard_regex() {
+        assert_eq!(wildcard_regex("example.com"), "((^)|(\\.))example\\.com$");
+        assert_eq!(wildcard_regex(".EXAMPLE.com"), "((^)|(\\.))example\\.com$");
+    }
+
+    #[test]
+    fn test_add_wildcard() {
+        let mut data = Vec::new();
+        write_eom(&mut data);
+
+        TestBuilder::new()
+            .endpoint("/admin/api/dns/wildcard")
+            .method(Method::Post)
+            .ftl("recompile-regex", data)
+            .file_expect(
+                PiholeFile::Regexlist,
+                "",
+                "((^)|(\\.))example\\.com$\n"
+            )
+            .body(json!({ "domain": "example.com" }))
+            .expect_json(
+                json!({
+                    "status": "success"
+                })
+            )
+            .test();
+    }
+
+    #[test]
+    fn test_delete_wildcard() {
+        let mut data = Vec::new();
+        write_eom(&mut data);
+
+        TestBuilder::new()
+            .endpoint("/admin/api/dns/wildcard/example.com")
+            .method(Method::Delete)
+            .ftl("recompile-regex", data)
+            .file_expect(
+                PiholeFile::Regexlist,
+                "((^)|(\\.))example\\.com$\n",
+                ""
+            )
+            .expect_json(
+                json!({
+                    "status": "success"
+                })
+            )
+            .test();
+    }
+}